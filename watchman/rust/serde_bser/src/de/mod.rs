@@ -0,0 +1,456 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Deserialize BSER into Rust data structures.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use serde::de::{self, Deserialize};
+use serde::forward_to_deserialize_any;
+
+use crate::errors::*;
+
+mod read;
+mod reentrant;
+pub mod variant;
+
+pub use self::read::{DeRead, SliceRead};
+
+use self::reentrant::ReentrantGuard;
+use self::variant::{SeqVariantAccess, UnitVariantAccess, VariantAccess};
+
+// BSER type markers.
+pub(crate) const BSER_ARRAY: u8 = 0x00;
+pub(crate) const BSER_OBJECT: u8 = 0x01;
+pub(crate) const BSER_BYTESTRING: u8 = 0x02;
+pub(crate) const BSER_INT8: u8 = 0x03;
+pub(crate) const BSER_INT16: u8 = 0x04;
+pub(crate) const BSER_INT32: u8 = 0x05;
+pub(crate) const BSER_INT64: u8 = 0x06;
+pub(crate) const BSER_REAL: u8 = 0x07;
+pub(crate) const BSER_TRUE: u8 = 0x08;
+pub(crate) const BSER_FALSE: u8 = 0x09;
+pub(crate) const BSER_NULL: u8 = 0x0a;
+pub(crate) const BSER_UTF8STRING: u8 = 0x0d;
+
+/// The default recursion limit applied to nested maps, arrays and enum
+/// variants when no other limit is configured.
+pub const DEFAULT_RECURSION_LIMIT: usize = 128;
+
+/// Tunables for a [`Deserializer`].
+#[derive(Clone, Debug)]
+pub struct Options {
+    recursion_limit: Option<usize>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            recursion_limit: Some(DEFAULT_RECURSION_LIMIT),
+        }
+    }
+}
+
+impl Options {
+    /// Default options, matching [`Deserializer::new`].
+    pub fn new() -> Self {
+        Options::default()
+    }
+
+    /// Set the recursion limit guarding nested maps, arrays and enum variants.
+    ///
+    /// Defaults to [`DEFAULT_RECURSION_LIMIT`]. Pass `None` to disable the cap
+    /// entirely when decoding trusted internal traffic; exceeding a configured
+    /// limit fails with [`Error::RecursionLimitExceeded`].
+    pub fn recursion_limit(mut self, limit: Option<usize>) -> Self {
+        self.recursion_limit = limit;
+        self
+    }
+}
+
+/// Deserialize an instance of `T` from a slice of BSER bytes.
+pub fn from_slice<'a, T>(slice: &'a [u8]) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let mut de = Deserializer::new(SliceRead::new(slice));
+    T::deserialize(&mut de)
+}
+
+/// A BSER deserializer reading from a [`DeRead`] source.
+pub struct Deserializer<R> {
+    read: R,
+    options: Options,
+    // Shared so every `ReentrantGuard` handed out for this document accounts
+    // against the same depth counter.
+    depth: Rc<Cell<usize>>,
+}
+
+impl<R> Deserializer<R> {
+    /// Create a deserializer with [`Options::default`].
+    pub fn new(read: R) -> Self {
+        Deserializer::with_options(read, Options::default())
+    }
+
+    /// Create a deserializer with the given [`Options`].
+    pub fn with_options(read: R, options: Options) -> Self {
+        Deserializer {
+            read,
+            options,
+            depth: Rc::new(Cell::new(0)),
+        }
+    }
+
+    /// Enter one level of nesting, consulting the configured recursion limit.
+    ///
+    /// The returned guard releases the level when dropped, so callers hold it
+    /// for the lifetime of the nested value. Fails with
+    /// [`Error::RecursionLimitExceeded`] once the limit is reached.
+    pub(crate) fn recursion_guard(&self) -> Result<ReentrantGuard> {
+        ReentrantGuard::new(self.depth.clone(), self.options.recursion_limit)
+    }
+}
+
+impl<'de, R> Deserializer<R>
+where
+    R: DeRead<'de>,
+{
+    fn parse_integer(&mut self) -> Result<i64> {
+        let marker = self.read.next()?;
+        self.parse_integer_body(marker)
+    }
+
+    fn parse_integer_body(&mut self, marker: u8) -> Result<i64> {
+        match marker {
+            BSER_INT8 => {
+                let b = self.read.read_bytes(1)?;
+                Ok(b[0] as i8 as i64)
+            }
+            BSER_INT16 => {
+                let b = self.read.read_bytes(2)?;
+                Ok(i16::from_le_bytes([b[0], b[1]]) as i64)
+            }
+            BSER_INT32 => {
+                let b = self.read.read_bytes(4)?;
+                Ok(i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as i64)
+            }
+            BSER_INT64 => {
+                let b = self.read.read_bytes(8)?;
+                Ok(i64::from_le_bytes([
+                    b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7],
+                ]))
+            }
+            other => Err(de::Error::custom(format!(
+                "expected an integer marker, found 0x{:02x}",
+                other
+            ))),
+        }
+    }
+
+    /// If the next BSER value is an integer, consume it and return it as a
+    /// variant index, failing with "variant index out of range" if it is
+    /// negative; otherwise leave the reader untouched so the tag can be read
+    /// as a name.
+    pub(crate) fn variant_index(&mut self) -> Result<Option<u64>> {
+        match self.read.peek()? {
+            BSER_INT8 | BSER_INT16 | BSER_INT32 | BSER_INT64 => {
+                let n = self.parse_integer()?;
+                let index = u64::try_from(n).map_err(|_| {
+                    <Error as de::Error>::custom(format!("variant index out of range: {}", n))
+                })?;
+                Ok(Some(index))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn parse_count(&mut self) -> Result<usize> {
+        let count = self.parse_integer()?;
+        usize::try_from(count)
+            .map_err(|_| <Error as de::Error>::custom("negative length in BSER input"))
+    }
+
+    fn parse_string_bytes(&mut self) -> Result<Vec<u8>> {
+        let len = self.parse_count()?;
+        self.read.read_bytes(len)
+    }
+}
+
+impl<'de, R> de::Deserializer<'de> for &mut Deserializer<R>
+where
+    R: DeRead<'de>,
+{
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let marker = self.read.next()?;
+        match marker {
+            BSER_NULL => visitor.visit_unit(),
+            BSER_TRUE => visitor.visit_bool(true),
+            BSER_FALSE => visitor.visit_bool(false),
+            BSER_INT8 | BSER_INT16 | BSER_INT32 | BSER_INT64 => {
+                visitor.visit_i64(self.parse_integer_body(marker)?)
+            }
+            BSER_REAL => {
+                let b = self.read.read_bytes(8)?;
+                visitor.visit_f64(f64::from_le_bytes([
+                    b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7],
+                ]))
+            }
+            BSER_BYTESTRING => {
+                let bytes = self.parse_string_bytes()?;
+                match String::from_utf8(bytes) {
+                    Ok(s) => visitor.visit_string(s),
+                    Err(err) => visitor.visit_byte_buf(err.into_bytes()),
+                }
+            }
+            BSER_UTF8STRING => {
+                let bytes = self.parse_string_bytes()?;
+                let s = String::from_utf8(bytes)
+                    .map_err(|_| <Error as de::Error>::custom("invalid UTF-8 string"))?;
+                visitor.visit_string(s)
+            }
+            BSER_ARRAY => {
+                let _guard = self.recursion_guard()?;
+                let count = self.parse_count()?;
+                visitor.visit_seq(SeqAccess::new(self, count))
+            }
+            BSER_OBJECT => {
+                let _guard = self.recursion_guard()?;
+                let count = self.parse_count()?;
+                visitor.visit_map(MapAccess::new(self, count))
+            }
+            other => Err(de::Error::custom(format!(
+                "unsupported BSER marker 0x{:02x}",
+                other
+            ))),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.read.peek()? == BSER_NULL {
+            self.read.next()?;
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let _guard = self.recursion_guard()?;
+        match self.read.next()? {
+            BSER_ARRAY => {
+                let count = self.parse_count()?;
+                visitor.visit_seq(SeqAccess::new(self, count))
+            }
+            other => Err(de::Error::custom(format!(
+                "expected an array, found 0x{:02x}",
+                other
+            ))),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let _guard = self.recursion_guard()?;
+        match self.read.next()? {
+            BSER_OBJECT => {
+                let count = self.parse_count()?;
+                visitor.visit_map(MapAccess::new(self, count))
+            }
+            other => Err(de::Error::custom(format!(
+                "expected an object, found 0x{:02x}",
+                other
+            ))),
+        }
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    /// Deserialize an enum from its BSER encoding.
+    ///
+    /// The externally-tagged form is a single-key object `{tag: payload}`,
+    /// dispatched through [`VariantAccess`]. A positional (array-tagged) form
+    /// `[tag, payload]` — with the payload omitted for unit variants — is
+    /// dispatched through [`SeqVariantAccess`], keeping enum-heavy protocol
+    /// messages compact. A bare scalar tag drives [`UnitVariantAccess`]. The
+    /// guard that accounts for the variant level is taken inside the access
+    /// constructor, so exactly one level is charged here.
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.read.peek()? {
+            BSER_ARRAY => {
+                self.read.next()?;
+                let count = self.parse_count()?;
+                if count == 0 {
+                    return Err(de::Error::invalid_length(
+                        count,
+                        &"an array whose first element names the variant",
+                    ));
+                }
+                // The tag is the first element; the rest carry the content.
+                visitor.visit_enum(SeqVariantAccess::new(self, variants, count - 1)?)
+            }
+            BSER_OBJECT => {
+                self.read.next()?;
+                let count = self.parse_count()?;
+                if count != 1 {
+                    return Err(de::Error::invalid_length(
+                        count,
+                        &"a single-key object naming the variant",
+                    ));
+                }
+                visitor.visit_enum(VariantAccess::new(self, variants)?)
+            }
+            _ => visitor.visit_enum(UnitVariantAccess::new(self, variants)),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct identifier ignored_any
+    }
+}
+
+/// Access to the elements of a BSER array.
+pub(crate) struct SeqAccess<'a, R> {
+    de: &'a mut Deserializer<R>,
+    remaining: usize,
+}
+
+impl<'a, R> SeqAccess<'a, R> {
+    pub(crate) fn new(de: &'a mut Deserializer<R>, remaining: usize) -> Self {
+        SeqAccess { de, remaining }
+    }
+}
+
+impl<'de, 'a, R> de::SeqAccess<'de> for SeqAccess<'a, R>
+where
+    R: DeRead<'de>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+/// Access to the entries of a BSER object.
+struct MapAccess<'a, R> {
+    de: &'a mut Deserializer<R>,
+    remaining: usize,
+}
+
+impl<'a, R> MapAccess<'a, R> {
+    fn new(de: &'a mut Deserializer<R>, remaining: usize) -> Self {
+        MapAccess { de, remaining }
+    }
+}
+
+impl<'de, 'a, R> de::MapAccess<'de> for MapAccess<'a, R>
+where
+    R: DeRead<'de>,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
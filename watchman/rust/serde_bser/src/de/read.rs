@@ -0,0 +1,72 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use serde::de;
+
+use crate::errors::*;
+
+/// A source of BSER bytes.
+///
+/// The `'de` lifetime ties a reader to its input so a `Deserializer<'de>` can be
+/// built over it. [`read_bytes`](DeRead::read_bytes) returns owned data, so the
+/// bundled [`SliceRead`] copies string and byte-string payloads out; the
+/// lifetime leaves room for a borrowing reader without changing this interface.
+pub trait DeRead<'de> {
+    /// Consume and return the next byte.
+    fn next(&mut self) -> Result<u8>;
+
+    /// Return the next byte without consuming it.
+    fn peek(&mut self) -> Result<u8>;
+
+    /// Consume exactly `len` bytes.
+    fn read_bytes(&mut self, len: usize) -> Result<Vec<u8>>;
+}
+
+/// A [`DeRead`] over an in-memory slice.
+pub struct SliceRead<'a> {
+    slice: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> SliceRead<'a> {
+    /// Wrap a byte slice.
+    pub fn new(slice: &'a [u8]) -> Self {
+        SliceRead { slice, pos: 0 }
+    }
+
+    fn eof(&self) -> Error {
+        de::Error::custom("unexpected end of BSER input")
+    }
+}
+
+impl<'de> DeRead<'de> for SliceRead<'de> {
+    fn next(&mut self) -> Result<u8> {
+        let byte = *self.slice.get(self.pos).ok_or_else(|| self.eof())?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn peek(&mut self) -> Result<u8> {
+        self.slice.get(self.pos).copied().ok_or_else(|| self.eof())
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<Vec<u8>> {
+        let end = self.pos.checked_add(len).ok_or_else(|| self.eof())?;
+        let bytes = self.slice.get(self.pos..end).ok_or_else(|| self.eof())?;
+        self.pos = end;
+        Ok(bytes.to_vec())
+    }
+}
@@ -0,0 +1,54 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::errors::*;
+
+/// RAII token accounting for one level of nesting against a shared depth
+/// counter.
+///
+/// The guard is created through [`Deserializer::recursion_guard`] so that it
+/// consults the configured [`Options::recursion_limit`]; it decrements the
+/// counter again when dropped. A `None` limit disables the cap entirely.
+///
+/// [`Deserializer::recursion_guard`]: super::Deserializer::recursion_guard
+/// [`Options::recursion_limit`]: super::Options::recursion_limit
+pub struct ReentrantGuard {
+    depth: Rc<Cell<usize>>,
+}
+
+impl ReentrantGuard {
+    /// Enter one level of nesting, failing with [`Error::RecursionLimitExceeded`]
+    /// if it would push `depth` past `limit`.
+    pub(super) fn new(depth: Rc<Cell<usize>>, limit: Option<usize>) -> Result<Self> {
+        let entered = depth.get() + 1;
+        if let Some(limit) = limit {
+            if entered > limit {
+                return Err(Error::RecursionLimitExceeded { limit });
+            }
+        }
+        depth.set(entered);
+        Ok(ReentrantGuard { depth })
+    }
+}
+
+impl Drop for ReentrantGuard {
+    fn drop(&mut self) {
+        self.depth.set(self.depth.get().saturating_sub(1));
+    }
+}
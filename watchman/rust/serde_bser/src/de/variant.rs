@@ -20,7 +20,7 @@ use crate::errors::*;
 
 use super::read::DeRead;
 use super::reentrant::ReentrantGuard;
-use super::Deserializer;
+use super::{Deserializer, SeqAccess};
 
 macro_rules! impl_enum_access {
     ($type:ident) => {
@@ -35,16 +35,52 @@ macro_rules! impl_enum_access {
             where
                 V: de::DeserializeSeed<'de>,
             {
-                let val = seed.deserialize(&mut *self.de)?;
+                // A numeric tag resolves positionally against the variant list;
+                // anything else is read as a variant name.
+                let val = match self.de.variant_index()? {
+                    Some(index) => variant_by_index(seed, index, self.variants)?,
+                    None => seed.deserialize(&mut *self.de)?,
+                };
                 Ok((val, self))
             }
         }
     };
 }
 
+/// Resolve a numeric variant tag against the `&'static [&'static str]` variant
+/// list passed to `deserialize_enum`, driving `seed` with `visit_u64(index)` so
+/// serde's generated field-identifier code maps it to the right variant.
+///
+/// Producers of compact binary messages commonly tag a variant by its integer
+/// position rather than by name; this accepts that positional discriminator
+/// while still decoding into named Rust enums. An index beyond the known
+/// variant count yields a clear "variant index out of range" error rather than
+/// letting the seed fail with an opaque message.
+pub fn variant_by_index<'de, V>(
+    seed: V,
+    index: u64,
+    variants: &'static [&'static str],
+) -> Result<V::Value>
+where
+    V: de::DeserializeSeed<'de>,
+{
+    if index as usize >= variants.len() {
+        return Err(de::Error::custom(format!(
+            "variant index out of range: {} (enum has {} variants)",
+            index,
+            variants.len()
+        )));
+    }
+    seed.deserialize(de::value::U64Deserializer::<Error>::new(index))
+}
+
 /// Deserialize access for unit, struct and tuple variants.
 pub struct VariantAccess<'a, R> {
     de: &'a mut Deserializer<R>,
+    variants: &'static [&'static str],
+    // Held for the lifetime of the access so the recursion limit accounts for
+    // the nested variant payload as well as the surrounding map or array.
+    _guard: ReentrantGuard,
 }
 
 impl<'a, 'de, R> VariantAccess<'a, R>
@@ -53,9 +89,20 @@ where
 {
     /// Create a new `VariantAccess`.
     ///
-    /// `_guard` makes sure the caller is accounting for the recursion limit.
-    pub fn new(de: &'a mut Deserializer<R>, _guard: &ReentrantGuard) -> Self {
-        VariantAccess { de }
+    /// `variants` is the list passed to `deserialize_enum`, used to resolve a
+    /// numeric tag to a variant by index. The recursion guard is taken from the
+    /// deserializer so that it consults the configured recursion limit (see
+    /// [`recursion_limit`](super::Options::recursion_limit)); a variant payload
+    /// nested past the limit fails with
+    /// [`RecursionLimitExceeded`](crate::errors::Error::RecursionLimitExceeded)
+    /// rather than overflowing the stack.
+    pub fn new(de: &'a mut Deserializer<R>, variants: &'static [&'static str]) -> Result<Self> {
+        let _guard = de.recursion_guard()?;
+        Ok(VariantAccess {
+            de,
+            variants,
+            _guard,
+        })
     }
 }
 
@@ -93,17 +140,126 @@ where
     }
 }
 
+/// Deserialize access for enums encoded positionally as a BSER array
+/// `[tag, a, b, ...]` rather than as the externally-tagged single-key map.
+///
+/// The tag (first element) names the variant, either as a string or as a
+/// numeric index resolved against the variant list, and the remaining elements
+/// carry the content positionally: a newtype variant pulls one element, tuple
+/// and struct variants pull one element per field, and a unit variant is a
+/// one-element `[tag]` array leaving nothing behind. This keeps enum-heavy
+/// protocol messages compact and makes the variant boundary explicit instead of
+/// relying on `deserialize_any` to guess it.
+pub struct SeqVariantAccess<'a, R> {
+    de: &'a mut Deserializer<R>,
+    variants: &'static [&'static str],
+    // Number of array elements left after the tag, so the content is pulled
+    // positionally and any leftover elements are rejected rather than dropped.
+    remaining: usize,
+    // See `VariantAccess`: held so the array payload counts against the limit.
+    _guard: ReentrantGuard,
+}
+
+impl<'a, 'de, R> SeqVariantAccess<'a, R>
+where
+    R: 'a + DeRead<'de>,
+{
+    /// Create a new `SeqVariantAccess`.
+    ///
+    /// `variants` is the list passed to `deserialize_enum`, used to resolve a
+    /// numeric tag to a variant by index. `remaining` is the number of array
+    /// elements that follow the tag. The recursion guard is taken from the
+    /// deserializer so that it consults the configured recursion limit (see
+    /// [`recursion_limit`](super::Options::recursion_limit)).
+    pub fn new(
+        de: &'a mut Deserializer<R>,
+        variants: &'static [&'static str],
+        remaining: usize,
+    ) -> Result<Self> {
+        let _guard = de.recursion_guard()?;
+        Ok(SeqVariantAccess {
+            de,
+            variants,
+            remaining,
+            _guard,
+        })
+    }
+}
+
+impl_enum_access!(SeqVariantAccess);
+
+impl<'a, 'de, R> de::VariantAccess<'de> for SeqVariantAccess<'a, R>
+where
+    R: 'a + DeRead<'de>,
+{
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        // A unit variant is a one-element `[tag]` array; the tag has already
+        // been consumed by `variant_seed`, so nothing must be left behind.
+        self.expect_remaining(0)
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        // Pull the single remaining array element as the content rather than
+        // re-reading a fresh value through `deserialize_any`.
+        self.expect_remaining(1)?;
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Consume the remaining outer-array elements positionally (`[tag, a, b]`)
+        // rather than requiring a nested array (`[tag, [a, b]]`).
+        self.expect_remaining(len)?;
+        visitor.visit_seq(SeqAccess::new(self.de, self.remaining))
+    }
+
+    fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Likewise pull one element per field positionally; serde's generated
+        // struct visitor accepts a sequence of field values.
+        self.expect_remaining(fields.len())?;
+        visitor.visit_seq(SeqAccess::new(self.de, self.remaining))
+    }
+}
+
+impl<'a, 'de, R> SeqVariantAccess<'a, R>
+where
+    R: 'a + DeRead<'de>,
+{
+    /// Ensure the array carries exactly `expected` content elements after the
+    /// tag, rejecting both missing and leftover elements.
+    fn expect_remaining(&self, expected: usize) -> Result<()> {
+        if self.remaining != expected {
+            let exp = format!("an array with {} element(s) after the variant tag", expected);
+            return Err(de::Error::invalid_length(self.remaining, &exp.as_str()));
+        }
+        Ok(())
+    }
+}
+
 /// Deserialize access for plain unit variants.
 pub struct UnitVariantAccess<'a, R> {
     de: &'a mut Deserializer<R>,
+    variants: &'static [&'static str],
 }
 
 impl<'a, 'de, R> UnitVariantAccess<'a, R>
 where
     R: 'a + DeRead<'de>,
 {
-    pub fn new(de: &'a mut Deserializer<R>) -> Self {
-        UnitVariantAccess { de }
+    /// `variants` is the list passed to `deserialize_enum`, used to resolve a
+    /// numeric tag to a variant by index.
+    pub fn new(de: &'a mut Deserializer<R>, variants: &'static [&'static str]) -> Self {
+        UnitVariantAccess { de, variants }
     }
 }
 
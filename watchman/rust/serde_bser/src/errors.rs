@@ -0,0 +1,77 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt::{self, Display};
+
+use serde::{de, ser};
+
+/// A specialized `Result` type for BSER (de)serialization.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors that can occur while (de)serializing BSER.
+#[derive(Debug)]
+pub enum Error {
+    /// A generic message produced by `serde` or by this crate.
+    Message(String),
+    /// An I/O error from the underlying reader or writer.
+    Io(std::io::Error),
+    /// The configured recursion limit was exceeded while decoding a deeply
+    /// nested document. The `limit` is the ceiling that was tripped; raise it
+    /// (or disable it with `None`) through [`Options::recursion_limit`] when
+    /// decoding trusted internal traffic.
+    ///
+    /// [`Options::recursion_limit`]: crate::de::Options::recursion_limit
+    RecursionLimitExceeded { limit: usize },
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Message(msg) => formatter.write_str(msg),
+            Error::Io(err) => write!(formatter, "I/O error: {}", err),
+            Error::RecursionLimitExceeded { limit } => {
+                write!(formatter, "recursion limit exceeded (limit: {})", limit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
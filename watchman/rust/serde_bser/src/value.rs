@@ -0,0 +1,356 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! An owned, dynamically-typed model of a BSER document.
+//!
+//! [`Value`] can be produced from any [`DeRead`] source (it implements
+//! [`serde::Deserialize`]) and in turn implements [`serde::Deserializer`], so a
+//! Watchman response can be inspected or transformed before being committed to
+//! a strongly-typed struct, or a subtree deserialized into a concrete type
+//! lazily. This mirrors the `Value` types shipped by the `toml` and `ron`
+//! formats.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::de;
+use serde::de::IntoDeserializer;
+use serde::forward_to_deserialize_any;
+
+use crate::errors::*;
+
+/// An owned, dynamically-typed BSER value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// The BSER null.
+    Null,
+    /// A boolean.
+    Bool(bool),
+    /// A signed integer.
+    Integer(i64),
+    /// A floating-point number.
+    Real(f64),
+    /// A UTF-8 string.
+    String(String),
+    /// A binary (byte) string.
+    Binary(Vec<u8>),
+    /// An array of values.
+    Array(Vec<Value>),
+    /// An object, keyed by string and preserving at most one binding per key.
+    Object(BTreeMap<String, Value>),
+}
+
+impl Value {
+    /// Describe this value for `serde`'s type-mismatch errors.
+    fn unexpected(&self) -> de::Unexpected<'_> {
+        match self {
+            Value::Null => de::Unexpected::Unit,
+            Value::Bool(b) => de::Unexpected::Bool(*b),
+            Value::Integer(n) => de::Unexpected::Signed(*n),
+            Value::Real(n) => de::Unexpected::Float(*n),
+            Value::String(s) => de::Unexpected::Str(s),
+            Value::Binary(b) => de::Unexpected::Bytes(b),
+            Value::Array(_) => de::Unexpected::Seq,
+            Value::Object(_) => de::Unexpected::Map,
+        }
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct ValueVisitor;
+
+        impl<'de> de::Visitor<'de> for ValueVisitor {
+            type Value = Value;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("any valid BSER value")
+            }
+
+            fn visit_bool<E>(self, v: bool) -> std::result::Result<Value, E> {
+                Ok(Value::Bool(v))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Value, E> {
+                Ok(Value::Integer(v))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(Value::Integer(i64::try_from(v).map_err(|_| {
+                    de::Error::custom(format!("integer {} out of range for i64", v))
+                })?))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> std::result::Result<Value, E> {
+                Ok(Value::Real(v))
+            }
+
+            fn visit_str<E>(self, v: &str) -> std::result::Result<Value, E> {
+                Ok(Value::String(v.to_owned()))
+            }
+
+            fn visit_string<E>(self, v: String) -> std::result::Result<Value, E> {
+                Ok(Value::String(v))
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> std::result::Result<Value, E> {
+                Ok(Value::Binary(v.to_owned()))
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> std::result::Result<Value, E> {
+                Ok(Value::Binary(v))
+            }
+
+            fn visit_none<E>(self) -> std::result::Result<Value, E> {
+                Ok(Value::Null)
+            }
+
+            fn visit_unit<E>(self) -> std::result::Result<Value, E> {
+                Ok(Value::Null)
+            }
+
+            fn visit_some<D>(self, deserializer: D) -> std::result::Result<Value, D::Error>
+            where
+                D: de::Deserializer<'de>,
+            {
+                de::Deserialize::deserialize(deserializer)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Value, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let mut array = Vec::new();
+                while let Some(elem) = seq.next_element()? {
+                    array.push(elem);
+                }
+                Ok(Value::Array(array))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> std::result::Result<Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                let mut object = BTreeMap::new();
+                while let Some((key, value)) = map.next_entry()? {
+                    object.insert(key, value);
+                }
+                Ok(Value::Object(object))
+            }
+        }
+
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+impl<'de> de::IntoDeserializer<'de, Error> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::Integer(n) => visitor.visit_i64(n),
+            Value::Real(n) => visitor.visit_f64(n),
+            Value::String(s) => visitor.visit_string(s),
+            Value::Binary(b) => visitor.visit_byte_buf(b),
+            Value::Array(array) => {
+                visitor.visit_seq(de::value::SeqDeserializer::new(array.into_iter()))
+            }
+            Value::Object(object) => {
+                visitor.visit_map(de::value::MapDeserializer::new(object.into_iter()))
+            }
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            Value::Null => visitor.visit_none(),
+            other => visitor.visit_some(other),
+        }
+    }
+
+    /// Deserialize an externally-tagged enum from a `Value`.
+    ///
+    /// A [`Value::String`] resolves to a unit variant and a single-key
+    /// [`Value::Object`] to a newtype, tuple or struct variant, whose content
+    /// is the object's one value: a tuple variant takes a [`Value::Array`], a
+    /// struct variant a [`Value::Object`], and a unit variant accepts no
+    /// content at all (not even [`Value::Null`]). Any other shape is a type
+    /// error. The tag is matched by name only: this does not go through the
+    /// streaming [`VariantAccess`](crate::de::variant::VariantAccess) path, so
+    /// numeric variant-index tags and the array-tagged `[tag, payload]` form
+    /// are not accepted here.
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let (variant, value) = match self {
+            Value::Object(object) => {
+                let mut iter = object.into_iter();
+                let (variant, value) = match iter.next() {
+                    Some(pair) => pair,
+                    None => {
+                        return Err(de::Error::invalid_value(
+                            de::Unexpected::Map,
+                            &"a single-key map naming the variant",
+                        ));
+                    }
+                };
+                if iter.next().is_some() {
+                    return Err(de::Error::invalid_value(
+                        de::Unexpected::Map,
+                        &"a single-key map naming the variant",
+                    ));
+                }
+                (variant, Some(value))
+            }
+            Value::String(variant) => (variant, None),
+            other => {
+                return Err(de::Error::invalid_type(
+                    other.unexpected(),
+                    &"a string or single-key map",
+                ));
+            }
+        };
+
+        visitor.visit_enum(EnumDeserializer { variant, value })
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct
+        map struct identifier ignored_any
+    }
+}
+
+/// [`EnumAccess`](de::EnumAccess) backed by a decomposed [`Value`] tag.
+struct EnumDeserializer {
+    variant: String,
+    value: Option<Value>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, VariantDeserializer)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = self.variant.into_deserializer();
+        let visitor = VariantDeserializer { value: self.value };
+        seed.deserialize(variant).map(|v| (v, visitor))
+    }
+}
+
+/// [`VariantAccess`](de::VariantAccess) for the content behind an enum tag.
+///
+/// A missing content (`value == None`) is the unit-variant case produced by a
+/// bare [`Value::String`] tag; asking it for a newtype, tuple or struct variant
+/// is rejected with `invalid_type(UnitVariant, ...)`, exactly as
+/// `UnitVariantAccess` does for the streaming deserializer.
+struct VariantDeserializer {
+    value: Option<Value>,
+}
+
+impl<'de> de::VariantAccess<'de> for VariantDeserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        match self.value {
+            None => Ok(()),
+            Some(value) => Err(de::Error::invalid_type(value.unexpected(), &"unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.value {
+            Some(value) => seed.deserialize(value),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Some(Value::Array(array)) => {
+                de::Deserializer::deserialize_any(Value::Array(array), visitor)
+            }
+            Some(other) => Err(de::Error::invalid_type(other.unexpected(), &"tuple variant")),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Some(Value::Object(object)) => {
+                de::Deserializer::deserialize_any(Value::Object(object), visitor)
+            }
+            Some(other) => Err(de::Error::invalid_type(other.unexpected(), &"struct variant")),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"struct variant",
+            )),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Positional (array-tagged) enum encoding.
+
+mod common;
+
+use common::{array, bstr, int};
+use serde::Deserialize;
+use serde_bser::from_slice;
+
+#[derive(Debug, Deserialize, PartialEq)]
+enum E {
+    Unit,
+    Newtype(i64),
+    Tuple(i64, i64),
+    Struct { a: i64, b: i64 },
+}
+
+#[test]
+fn unit_from_single_element_array() {
+    assert_eq!(from_slice::<E>(&array(vec![bstr("Unit")])).unwrap(), E::Unit);
+}
+
+#[test]
+fn newtype_pulls_remaining_element() {
+    assert_eq!(
+        from_slice::<E>(&array(vec![bstr("Newtype"), int(7)])).unwrap(),
+        E::Newtype(7)
+    );
+}
+
+#[test]
+fn tuple_pulls_remaining_elements_positionally() {
+    // `[tag, a, b]`, not `[tag, [a, b]]`.
+    assert_eq!(
+        from_slice::<E>(&array(vec![bstr("Tuple"), int(1), int(2)])).unwrap(),
+        E::Tuple(1, 2)
+    );
+}
+
+#[test]
+fn struct_pulls_remaining_elements_positionally() {
+    assert_eq!(
+        from_slice::<E>(&array(vec![bstr("Struct"), int(3), int(4)])).unwrap(),
+        E::Struct { a: 3, b: 4 }
+    );
+}
+
+#[test]
+fn unit_rejects_trailing_elements() {
+    // `[ "Unit", 42 ]` must not silently drop the trailing `42`.
+    assert!(from_slice::<E>(&array(vec![bstr("Unit"), int(42)])).is_err());
+}
+
+#[test]
+fn newtype_rejects_extra_elements() {
+    assert!(from_slice::<E>(&array(vec![bstr("Newtype"), int(1), int(2)])).is_err());
+}
+
+#[test]
+fn tuple_rejects_wrong_arity() {
+    assert!(from_slice::<E>(&array(vec![bstr("Tuple"), int(1)])).is_err());
+}
@@ -0,0 +1,55 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Minimal hand-rolled BSER encoders shared by the deserializer tests.
+
+#![allow(dead_code)]
+
+/// Encode a signed integer (always as an `int64` for simplicity).
+pub fn int(n: i64) -> Vec<u8> {
+    let mut v = vec![0x06];
+    v.extend_from_slice(&n.to_le_bytes());
+    v
+}
+
+/// Encode a UTF-8 string as a BSER byte string.
+pub fn bstr(s: &str) -> Vec<u8> {
+    let mut v = vec![0x02];
+    v.extend(int(s.len() as i64));
+    v.extend_from_slice(s.as_bytes());
+    v
+}
+
+/// Encode an array from already-encoded elements.
+pub fn array(items: Vec<Vec<u8>>) -> Vec<u8> {
+    let mut v = vec![0x00];
+    v.extend(int(items.len() as i64));
+    for item in items {
+        v.extend(item);
+    }
+    v
+}
+
+/// Encode an object from already-encoded values.
+pub fn object(entries: Vec<(&str, Vec<u8>)>) -> Vec<u8> {
+    let mut v = vec![0x01];
+    v.extend(int(entries.len() as i64));
+    for (key, value) in entries {
+        v.extend(bstr(key));
+        v.extend(value);
+    }
+    v
+}
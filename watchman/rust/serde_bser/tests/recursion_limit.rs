@@ -0,0 +1,56 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Configurable recursion limit.
+
+mod common;
+
+use common::{array, int};
+use serde::Deserialize;
+use serde_bser::de::SliceRead;
+use serde_bser::value::Value;
+use serde_bser::{Deserializer, Options};
+
+fn decode(bytes: &[u8], limit: Option<usize>) -> serde_bser::Result<Value> {
+    let mut de = Deserializer::with_options(SliceRead::new(bytes), Options::new().recursion_limit(limit));
+    Value::deserialize(&mut de)
+}
+
+#[test]
+fn limit_trips_on_too_deep_nesting() {
+    // `[[[1]]]` is three levels of array.
+    let deep = array(vec![array(vec![array(vec![int(1)])])]);
+    let err = decode(&deep, Some(2)).unwrap_err();
+    assert!(
+        err.to_string().contains("recursion limit exceeded"),
+        "unexpected error: {}",
+        err
+    );
+}
+
+#[test]
+fn limit_none_disables_the_cap() {
+    let deep = array(vec![array(vec![array(vec![int(1)])])]);
+    assert!(decode(&deep, None).is_ok());
+}
+
+#[test]
+fn sibling_guards_are_released() {
+    // `[[1], [2]]` is only two levels deep; the first sibling's guard must be
+    // released before the second is entered, so a limit of 2 accepts it.
+    let siblings = array(vec![array(vec![int(1)]), array(vec![int(2)])]);
+    assert!(decode(&siblings, Some(2)).is_ok());
+}
@@ -0,0 +1,70 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The owned `bser::Value` model and its `Deserializer` impl.
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::{int, object};
+use serde::Deserialize;
+use serde_bser::from_slice;
+use serde_bser::value::Value;
+
+#[derive(Debug, Deserialize, PartialEq)]
+enum E {
+    Unit,
+    Newtype(i64),
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Id(i64);
+
+#[test]
+fn produced_from_a_deread_source() {
+    let v: Value = from_slice(&object(vec![("b", int(5))])).unwrap();
+    let mut expected = BTreeMap::new();
+    expected.insert("b".to_string(), Value::Integer(5));
+    assert_eq!(v, Value::Object(expected));
+}
+
+#[test]
+fn string_deserializes_into_unit_variant() {
+    assert_eq!(E::deserialize(Value::String("Unit".into())).unwrap(), E::Unit);
+}
+
+#[test]
+fn single_key_object_deserializes_into_newtype_variant() {
+    let mut map = BTreeMap::new();
+    map.insert("Newtype".to_string(), Value::Integer(9));
+    assert_eq!(E::deserialize(Value::Object(map)).unwrap(), E::Newtype(9));
+}
+
+#[test]
+fn non_string_non_map_is_rejected_as_invalid_type() {
+    let err = E::deserialize(Value::Integer(3)).unwrap_err();
+    assert!(
+        err.to_string().contains("invalid type"),
+        "unexpected error: {}",
+        err
+    );
+}
+
+#[test]
+fn newtype_struct_wraps_a_scalar() {
+    assert_eq!(Id::deserialize(Value::Integer(42)).unwrap(), Id(42));
+}
@@ -0,0 +1,55 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Numeric variant-index tags.
+
+mod common;
+
+use common::{array, int};
+use serde::Deserialize;
+use serde_bser::from_slice;
+
+#[derive(Debug, Deserialize, PartialEq)]
+enum E {
+    Zero,
+    One(i64),
+}
+
+#[test]
+fn numeric_index_resolves_to_unit_variant() {
+    // Index 0 -> `Zero`.
+    assert_eq!(from_slice::<E>(&array(vec![int(0)])).unwrap(), E::Zero);
+}
+
+#[test]
+fn numeric_index_resolves_to_payload_variant() {
+    // Index 1 -> `One(42)`.
+    assert_eq!(
+        from_slice::<E>(&array(vec![int(1), int(42)])).unwrap(),
+        E::One(42)
+    );
+}
+
+#[test]
+fn out_of_range_index_is_rejected() {
+    // Only two variants, so index 2 is out of range.
+    let err = from_slice::<E>(&array(vec![int(2), int(0)])).unwrap_err();
+    assert!(
+        err.to_string().contains("variant index out of range"),
+        "unexpected error: {}",
+        err
+    );
+}